@@ -0,0 +1,211 @@
+//! Hot-reloadable compiler configuration.
+//!
+//! Settings that used to be hard-coded — the `g++` binary and its flags, the
+//! execution timeout, the max source size, and which `#include` headers
+//! codegen is allowed to emit — live in a versioned TOML file instead. A
+//! [`Config`] is loaded once at startup and then kept live behind an
+//! [`ArcSwap`] so in-flight requests always see a consistent snapshot while
+//! [`spawn_config_watcher_system`] swaps in a fresh one whenever the file
+//! changes, letting operators retune limits or swap `g++` for `clang++`
+//! without restarting the server.
+
+use arc_swap::ArcSwap;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+/// Bumped whenever the TOML schema changes, so a future loader can migrate
+/// older files instead of rejecting them outright.
+pub const CURRENT_CONFIG_VERSION: u32 = 1;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Config {
+    pub version: u32,
+    pub port: u16,
+    pub compiler: CompilerConfig,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CompilerConfig {
+    pub executable: String,
+    pub flags: Vec<String>,
+    pub exec_timeout_secs: u64,
+    pub max_source_bytes: usize,
+    pub allowed_includes: Vec<String>,
+}
+
+impl Config {
+    fn load_from(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| format!("failed to read {path:?}: {e}"))?;
+        let config: Config = toml::from_str(&text).map_err(|e| format!("invalid config at {path:?}: {e}"))?;
+        Ok(config.migrate())
+    }
+
+    /// No schema migrations exist yet; future version bumps land here.
+    fn migrate(self) -> Self {
+        if self.version != CURRENT_CONFIG_VERSION {
+            eprintln!(
+                "[flow-chat] config: file version {} differs from current {}; no migrations defined, using as-is",
+                self.version, CURRENT_CONFIG_VERSION
+            );
+        }
+        self
+    }
+
+    pub fn exec_timeout(&self) -> Duration {
+        Duration::from_secs(self.compiler.exec_timeout_secs)
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            version: CURRENT_CONFIG_VERSION,
+            port: 9602,
+            compiler: CompilerConfig {
+                executable: "g++".to_string(),
+                flags: vec!["-std=c++17".to_string()],
+                exec_timeout_secs: 5,
+                max_source_bytes: 64 * 1024,
+                allowed_includes: vec![
+                    "iostream".to_string(),
+                    "string".to_string(),
+                    "cmath".to_string(),
+                    "array".to_string(),
+                ],
+            },
+        }
+    }
+}
+
+/// Load `path`, falling back to [`Config::default`] (and logging why) if the
+/// file is missing or malformed, so a bad config never keeps the server from
+/// starting.
+pub fn load_or_default(path: &Path) -> Config {
+    Config::load_from(path).unwrap_or_else(|e| {
+        eprintln!("[flow-chat] config: {e}; using defaults");
+        Config::default()
+    })
+}
+
+/// A [`Config`] shared across requests, hot-swappable by
+/// [`spawn_config_watcher_system`] without locking readers out.
+pub struct SharedConfig(ArcSwap<Config>);
+
+impl SharedConfig {
+    pub fn new(initial: Config) -> Self {
+        SharedConfig(ArcSwap::new(Arc::new(initial)))
+    }
+
+    pub fn load(&self) -> Arc<Config> {
+        self.0.load_full()
+    }
+}
+
+/// Poll `path`'s mtime every [`POLL_INTERVAL`] and reload it into `shared`
+/// whenever it changes. Reload failures are logged and the previous config
+/// is kept, so a typo in the file can't take the server down.
+pub fn spawn_config_watcher_system(path: PathBuf, shared: Arc<SharedConfig>) {
+    tokio::spawn(async move {
+        let mut last_modified = file_mtime(&path);
+        let mut interval = tokio::time::interval(POLL_INTERVAL);
+        loop {
+            interval.tick().await;
+
+            let modified = file_mtime(&path);
+            if modified.is_none() || modified == last_modified {
+                continue;
+            }
+            last_modified = modified;
+
+            match Config::load_from(&path) {
+                Ok(config) => {
+                    println!("[flow-chat] config reloaded from {path:?}");
+                    shared.0.store(Arc::new(config));
+                }
+                Err(e) => eprintln!("[flow-chat] config reload failed, keeping previous config: {e}"),
+            }
+        }
+    });
+}
+
+fn file_mtime(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn toml_with_port(port: u16) -> String {
+        format!(
+            r#"
+            version = 1
+            port = {port}
+
+            [compiler]
+            executable = "g++"
+            flags = ["-std=c++17"]
+            exec_timeout_secs = 5
+            max_source_bytes = 1024
+            allowed_includes = ["iostream"]
+            "#
+        )
+    }
+
+    #[test]
+    fn test_load_or_default_falls_back_when_missing() {
+        let config = load_or_default(Path::new("/nonexistent/flow_chat_test_config.toml"));
+        assert_eq!(config.version, CURRENT_CONFIG_VERSION);
+        assert_eq!(config.compiler.executable, "g++");
+    }
+
+    #[test]
+    fn test_load_from_parses_valid_toml() {
+        let dir = std::env::temp_dir().join(format!("flow_chat_config_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("flow_chat.toml");
+        std::fs::write(&path, toml_with_port(1234)).unwrap();
+
+        let config = load_or_default(&path);
+        assert_eq!(config.port, 1234);
+        assert_eq!(config.compiler.executable, "g++");
+        assert_eq!(config.exec_timeout(), Duration::from_secs(5));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_spawn_config_watcher_system_reloads_on_change() {
+        let dir = std::env::temp_dir().join(format!("flow_chat_config_watch_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("flow_chat.toml");
+        std::fs::write(&path, toml_with_port(1111)).unwrap();
+
+        let shared = Arc::new(SharedConfig::new(load_or_default(&path)));
+        assert_eq!(shared.load().port, 1111);
+
+        spawn_config_watcher_system(path.clone(), shared.clone());
+        // Give the watcher task a chance to run and capture the file's
+        // current mtime before we change it, so the write below always
+        // lands after that initial snapshot rather than racing it.
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        std::fs::write(&path, toml_with_port(2222)).unwrap();
+
+        let mut reloaded = false;
+        for _ in 0..100 {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            if shared.load().port == 2222 {
+                reloaded = true;
+                break;
+            }
+        }
+        assert!(reloaded, "watcher did not pick up the config change");
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
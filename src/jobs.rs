@@ -0,0 +1,202 @@
+//! Sandboxed compile-and-run jobs.
+//!
+//! Each job gets its own scratch directory (keyed by `compilation_id`, so
+//! concurrent requests never share a path) and runs g++ plus the resulting
+//! binary on a dedicated blocking thread pool sized from the machine's core
+//! count, rather than occupying a Tokio async worker. A wall-clock timeout
+//! guards against runaway Flow programs (e.g. `while true do`): on expiry
+//! the whole process group is killed so no stray child survives the job.
+
+use std::io::Read;
+use std::os::unix::process::CommandExt;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+use threadpool::ThreadPool;
+use tokio::sync::oneshot;
+
+#[derive(Debug, Clone)]
+pub enum RunOutcome {
+    Success { stdout: String },
+    RuntimeError { stderr: String },
+    CompileError { stderr: String },
+    TimedOut { partial_stdout: String },
+    Unavailable { message: String },
+}
+
+fn worker_pool() -> &'static ThreadPool {
+    static POOL: OnceLock<ThreadPool> = OnceLock::new();
+    POOL.get_or_init(|| ThreadPool::new(num_cpus::get().max(1)))
+}
+
+/// Write `cpp_source` to a per-job scratch directory, compile it with
+/// `executable` (`g++`, `clang++`, ...) under `flags`, and run the result
+/// under `timeout`. Runs on the dedicated worker pool so the caller's async
+/// task isn't blocked while the compiler or the binary runs.
+pub async fn compile_and_run(
+    compilation_id: u64,
+    cpp_source: String,
+    executable: &str,
+    flags: &[String],
+    timeout: Duration,
+) -> RunOutcome {
+    let (tx, rx) = oneshot::channel();
+    let dir = job_dir(compilation_id);
+    let executable = executable.to_string();
+    let flags = flags.to_vec();
+
+    worker_pool().execute(move || {
+        let outcome = run_job(&dir, &cpp_source, &executable, &flags, timeout);
+        let _ = std::fs::remove_dir_all(&dir);
+        let _ = tx.send(outcome);
+    });
+
+    rx.await.unwrap_or(RunOutcome::Unavailable { message: "compilation worker dropped".into() })
+}
+
+fn job_dir(compilation_id: u64) -> PathBuf {
+    std::env::temp_dir().join(format!("flow_chat_job_{compilation_id}"))
+}
+
+fn run_job(dir: &Path, cpp_source: &str, executable: &str, flags: &[String], timeout: Duration) -> RunOutcome {
+    if std::fs::create_dir_all(dir).is_err() {
+        return RunOutcome::Unavailable { message: "failed to create job directory".into() };
+    }
+
+    let src_path = dir.join("main.cpp");
+    let bin_path = dir.join("main");
+
+    if std::fs::write(&src_path, cpp_source).is_err() {
+        return RunOutcome::Unavailable { message: "failed to write source file".into() };
+    }
+
+    match Command::new(executable).args(flags).arg("-o").arg(&bin_path).arg(&src_path).output() {
+        Ok(out) if out.status.success() => run_with_timeout(&bin_path, timeout),
+        Ok(out) => RunOutcome::CompileError { stderr: String::from_utf8_lossy(&out.stderr).to_string() },
+        Err(_) => RunOutcome::Unavailable { message: format!("{executable} not found") },
+    }
+}
+
+fn run_with_timeout(bin_path: &Path, timeout: Duration) -> RunOutcome {
+    let mut child = match Command::new(bin_path)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .process_group(0) // own pgid == own pid, so we can kill the whole group on timeout
+        .spawn()
+    {
+        Ok(c) => c,
+        Err(e) => return RunOutcome::Unavailable { message: format!("failed to run: {e}") },
+    };
+
+    let pid = child.id() as i32;
+    let mut stdout_pipe = child.stdout.take().expect("stdout piped at spawn");
+    let mut stderr_pipe = child.stderr.take().expect("stderr piped at spawn");
+
+    // Drain both pipes on their own threads so a chatty program can't block
+    // on a full pipe buffer while we're busy polling for exit.
+    let stdout_reader = std::thread::spawn(move || {
+        let mut buf = String::new();
+        let _ = stdout_pipe.read_to_string(&mut buf);
+        buf
+    });
+    let stderr_reader = std::thread::spawn(move || {
+        let mut buf = String::new();
+        let _ = stderr_pipe.read_to_string(&mut buf);
+        buf
+    });
+
+    let start = Instant::now();
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break Some(status),
+            Ok(None) if start.elapsed() >= timeout => break None,
+            Ok(None) => std::thread::sleep(Duration::from_millis(25)),
+            Err(_) => break None,
+        }
+    };
+
+    if status.is_none() {
+        // SAFETY: `pid` is this child's own pid and, via process_group(0),
+        // also its pgid; killing `-pid` signals the group, not our own.
+        unsafe {
+            libc::kill(-pid, libc::SIGKILL);
+        }
+        let _ = child.wait();
+    }
+
+    let stdout = stdout_reader.join().unwrap_or_default();
+    let stderr = stderr_reader.join().unwrap_or_default();
+
+    match status {
+        Some(s) if s.success() => RunOutcome::Success { stdout },
+        Some(_) => RunOutcome::RuntimeError { stderr },
+        None => RunOutcome::TimedOut { partial_stdout: stdout },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cpp_echo(text: &str) -> String {
+        format!("#include <iostream>\nint main() {{ std::cout << \"{text}\"; return 0; }}\n")
+    }
+
+    fn cpp_infinite_loop() -> String {
+        "int main() { while (true) {} return 0; }\n".to_string()
+    }
+
+    fn gpp_flags() -> Vec<String> {
+        vec!["-std=c++17".to_string()]
+    }
+
+    #[tokio::test]
+    async fn test_compile_and_run_success() {
+        let outcome =
+            compile_and_run(900_001, cpp_echo("hello"), "g++", &gpp_flags(), Duration::from_secs(5)).await;
+        match outcome {
+            RunOutcome::Success { stdout } => assert_eq!(stdout, "hello"),
+            other => panic!("expected success, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_compile_and_run_reports_compile_errors() {
+        let outcome = compile_and_run(
+            900_002,
+            "int main() { return not_valid_cpp; }\n".to_string(),
+            "g++",
+            &gpp_flags(),
+            Duration::from_secs(5),
+        )
+        .await;
+        match outcome {
+            RunOutcome::CompileError { stderr } => assert!(stderr.contains("not_valid_cpp")),
+            other => panic!("expected a compile error, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_compile_and_run_kills_runaway_process_on_timeout() {
+        let start = Instant::now();
+        let outcome = compile_and_run(
+            900_003,
+            cpp_infinite_loop(),
+            "g++",
+            &gpp_flags(),
+            Duration::from_millis(300),
+        )
+        .await;
+        assert!(matches!(outcome, RunOutcome::TimedOut { .. }));
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn test_compile_and_run_cleans_up_job_dir() {
+        let id = 900_004;
+        let outcome = compile_and_run(id, cpp_echo("x"), "g++", &gpp_flags(), Duration::from_secs(5)).await;
+        assert!(matches!(outcome, RunOutcome::Success { .. }));
+        assert!(!job_dir(id).exists());
+    }
+}
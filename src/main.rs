@@ -1,15 +1,23 @@
+mod config;
+mod jobs;
+mod session;
 mod transpiler;
 
 use axum::{
     Router,
     extract::ws::{Message, WebSocket, WebSocketUpgrade},
+    extract::State,
     http::StatusCode,
     response::{Html, IntoResponse, Json},
     routing::{get, post},
 };
+use config::SharedConfig;
+use jobs::RunOutcome;
 use serde::{Deserialize, Serialize};
-use std::process::Command;
+use std::path::PathBuf;
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
 use tokio::net::TcpListener;
 use tower_http::services::ServeDir;
 
@@ -38,6 +46,7 @@ struct CompileResponse {
     output: String,
     success: bool,
     compilation_id: u64,
+    diagnostics: Vec<transpiler::Diagnostic>,
 }
 
 async fn health() -> Json<HealthResponse> {
@@ -50,60 +59,122 @@ async fn health() -> Json<HealthResponse> {
     })
 }
 
-async fn compile_flow(Json(req): Json<CompileRequest>) -> Json<CompileResponse> {
+async fn compile_flow(
+    State(config): State<Arc<SharedConfig>>,
+    Json(req): Json<CompileRequest>,
+) -> Json<CompileResponse> {
+    let config = config.load();
+
+    if let Some((output, diagnostics)) = oversized_source_error(&req.source, &config) {
+        return Json(CompileResponse { cpp: String::new(), output, success: false, compilation_id: 0, diagnostics });
+    }
+
     let id = COMPILATIONS.fetch_add(1, Ordering::Relaxed) + 1;
-    let cpp = transpiler::transpile(&req.source);
 
-    // Try to compile and run
-    let (output, success) = compile_and_run(&cpp);
+    let (cpp, output, success, diagnostics) = match transpiler::compile(&req.source, &config.compiler.allowed_includes) {
+        Ok(compiled) => {
+            let outcome = run_sandboxed(id, compiled.cpp.clone(), &config).await;
+            let (output, success, diagnostics) =
+                format_run_outcome(&outcome, config.exec_timeout(), &compiled.source_map);
+            (compiled.cpp, output, success, diagnostics)
+        }
+        Err(diagnostics) => {
+            let output = compile_error_output(&diagnostics);
+            (String::new(), output, false, diagnostics)
+        }
+    };
 
     Json(CompileResponse {
         cpp,
         output,
         success,
         compilation_id: id,
+        diagnostics,
     })
 }
 
-fn compile_and_run(cpp_source: &str) -> (String, bool) {
-    let src_path = "/tmp/flow_chat_tmp.cpp";
-    let bin_path = "/tmp/flow_chat_tmp";
+/// Reject sources over the configured size limit before they ever reach the
+/// parser, returning the `(output message, diagnostics)` pair callers embed
+/// in their response.
+fn oversized_source_error(source: &str, config: &config::Config) -> Option<(String, Vec<transpiler::Diagnostic>)> {
+    oversized_len_error(source.len(), config)
+}
 
-    if std::fs::write(src_path, cpp_source).is_err() {
-        return ("Failed to write temp file".into(), false);
+/// Same check as [`oversized_source_error`], but against an already-known
+/// byte length — used for the WebSocket REPL, where the limit has to apply
+/// to the cumulative session program's rendered C++, not just the latest
+/// message, or a client could grow an unbounded program across many
+/// under-the-limit messages.
+fn oversized_len_error(len: usize, config: &config::Config) -> Option<(String, Vec<transpiler::Diagnostic>)> {
+    if len <= config.compiler.max_source_bytes {
+        return None;
     }
+    Some((
+        format!(
+            "Source exceeds the configured max size of {} bytes",
+            config.compiler.max_source_bytes
+        ),
+        Vec::new(),
+    ))
+}
 
-    match Command::new("g++")
-        .args(["-std=c++17", "-o", bin_path, src_path])
-        .output()
-    {
-        Ok(out) if out.status.success() => {
-            match Command::new(bin_path).output() {
-                Ok(run) => {
-                    let stdout = String::from_utf8_lossy(&run.stdout).to_string();
-                    let stderr = String::from_utf8_lossy(&run.stderr).to_string();
-                    if run.status.success() {
-                        (if stdout.is_empty() { "(no output)".into() } else { stdout }, true)
-                    } else {
-                        (format!("Runtime error:\n{stderr}"), false)
-                    }
-                }
-                Err(e) => (format!("Failed to run: {e}"), false),
-            }
+/// Render an `output` string for a `compile`/`compile_program` failure from
+/// its actual diagnostics — by the time `compile_program` runs, the only
+/// possible failures are semantic errors or a disallowed include, never a
+/// parse error, so a literal `"Parse error"` here would contradict the
+/// diagnostics sent alongside it.
+fn compile_error_output(diagnostics: &[transpiler::Diagnostic]) -> String {
+    if diagnostics.is_empty() {
+        return "Compile error".to_string();
+    }
+    let lines: Vec<String> =
+        diagnostics.iter().map(|d| format!("{}:{}: {}", d.flow_line, d.flow_col, d.message)).collect();
+    format!("Compile error:\n{}", lines.join("\n"))
+}
+
+/// Run `cpp_source` in its own sandboxed job using the executable, flags,
+/// and timeout the active config specifies.
+async fn run_sandboxed(id: u64, cpp_source: String, config: &config::Config) -> RunOutcome {
+    let timeout = config.exec_timeout();
+    jobs::compile_and_run(id, cpp_source, &config.compiler.executable, &config.compiler.flags, timeout).await
+}
+
+/// Translate a job outcome into the `(output, success, diagnostics)` shape
+/// the handlers send back to clients.
+fn format_run_outcome(
+    outcome: &RunOutcome,
+    timeout: Duration,
+    source_map: &[transpiler::SourceMapEntry],
+) -> (String, bool, Vec<transpiler::Diagnostic>) {
+    match outcome {
+        RunOutcome::Success { stdout } => {
+            (if stdout.is_empty() { "(no output)".to_string() } else { stdout.clone() }, true, Vec::new())
         }
-        Ok(out) => {
-            let stderr = String::from_utf8_lossy(&out.stderr).to_string();
-            (format!("Compilation error:\n{stderr}"), false)
+        RunOutcome::RuntimeError { stderr } => (format!("Runtime error:\n{stderr}"), false, Vec::new()),
+        RunOutcome::CompileError { stderr } => {
+            let diagnostics = transpiler::diagnostics_from_gpp_stderr(stderr, source_map);
+            (format!("Compilation error:\n{stderr}"), false, diagnostics)
         }
-        Err(_) => ("g++ not found".into(), false),
+        RunOutcome::TimedOut { partial_stdout } => (
+            format!("Timed out after {timeout:?}\nPartial output:\n{partial_stdout}"),
+            false,
+            Vec::new(),
+        ),
+        RunOutcome::Unavailable { message } => (message.clone(), false, Vec::new()),
     }
 }
 
-async fn ws_handler(ws: WebSocketUpgrade) -> impl IntoResponse {
-    ws.on_upgrade(handle_ws)
+async fn ws_handler(State(config): State<Arc<SharedConfig>>, ws: WebSocketUpgrade) -> impl IntoResponse {
+    ws.on_upgrade(move |socket| handle_ws(socket, config))
 }
 
-async fn handle_ws(mut socket: WebSocket) {
+/// Drive one WebSocket connection as a stateful Flow REPL: each message's
+/// top-level statements are appended to a per-connection [`session::Session`]
+/// and the *whole* accumulated program is recompiled and rerun, so a
+/// `define` or `let` from an earlier message stays in scope. `:reset` clears
+/// the session and `:undo` drops the last statement; only the output
+/// produced by newly added code is echoed back.
+async fn handle_ws(mut socket: WebSocket, config: Arc<SharedConfig>) {
     let welcome = serde_json::json!({
         "type": "info",
         "message": "FLOW COMPILER v2.0 — Flow-to-C++17. Type Flow code.",
@@ -113,24 +184,112 @@ async fn handle_ws(mut socket: WebSocket) {
         .send(Message::Text(welcome.to_string().into()))
         .await;
 
+    let mut session = session::Session::new();
+
     while let Some(Ok(msg)) = socket.recv().await {
         if let Message::Text(text) = msg {
-            let source = text.trim().to_string();
-            if source.is_empty() {
+            let input = text.trim().to_string();
+            if input.is_empty() {
+                continue;
+            }
+
+            if input == ":reset" {
+                session.reset();
+                let resp = serde_json::json!({ "type": "reset" });
+                if socket.send(Message::Text(resp.to_string())).await.is_err() {
+                    break;
+                }
                 continue;
             }
 
+            if input == ":undo" {
+                let removed = session.undo();
+                if removed {
+                    refresh_session_stdout(&mut session, &config.load()).await;
+                }
+                let resp = serde_json::json!({ "type": "undo", "removed": removed });
+                if socket.send(Message::Text(resp.to_string())).await.is_err() {
+                    break;
+                }
+                continue;
+            }
+
+            let config = config.load();
+            if let Some((output, diagnostics)) = oversized_source_error(&input, &config) {
+                let resp = serde_json::json!({
+                    "type": "compiled",
+                    "flow": input,
+                    "cpp": "",
+                    "output": output,
+                    "compiled": false,
+                    "compilation_id": 0,
+                    "diagnostics": diagnostics,
+                });
+                if socket.send(Message::Text(resp.to_string())).await.is_err() {
+                    break;
+                }
+                continue;
+            }
+
+            let fragment = match transpiler::parse(&input) {
+                Ok(fragment) => fragment,
+                Err(diagnostics) => {
+                    let resp = serde_json::json!({
+                        "type": "compiled",
+                        "flow": input,
+                        "cpp": "",
+                        "output": "Parse error",
+                        "compiled": false,
+                        "compilation_id": 0,
+                        "diagnostics": diagnostics,
+                    });
+                    if socket.send(Message::Text(resp.to_string())).await.is_err() {
+                        break;
+                    }
+                    continue;
+                }
+            };
+            let candidate = session.with_fragment(&fragment);
+
             let id = COMPILATIONS.fetch_add(1, Ordering::Relaxed) + 1;
-            let cpp = transpiler::transpile(&source);
-            let (output, success) = compile_and_run(&cpp);
+            let (cpp, output, success, diagnostics) =
+                match transpiler::compile_program(&candidate, &config.compiler.allowed_includes) {
+                    Ok(compiled) => {
+                        // The cumulative session program can grow past the
+                        // configured limit even though every individual
+                        // message stayed under it — check the rendered
+                        // cpp, not just the message that was just parsed.
+                        if let Some((output, diagnostics)) = oversized_len_error(compiled.cpp.len(), &config) {
+                            (String::new(), output, false, diagnostics)
+                        } else {
+                            let outcome = run_sandboxed(id, compiled.cpp.clone(), &config).await;
+                            if let RunOutcome::Success { stdout } = &outcome {
+                                let new_output = session.new_output(stdout).to_string();
+                                session.commit(candidate, stdout.clone());
+                                let display =
+                                    if new_output.is_empty() { "(no output)".to_string() } else { new_output };
+                                (compiled.cpp, display, true, Vec::new())
+                            } else {
+                                let (output, _, diagnostics) =
+                                    format_run_outcome(&outcome, config.exec_timeout(), &compiled.source_map);
+                                (compiled.cpp, output, false, diagnostics)
+                            }
+                        }
+                    }
+                    Err(diagnostics) => {
+                        let output = compile_error_output(&diagnostics);
+                        (String::new(), output, false, diagnostics)
+                    }
+                };
 
             let resp = serde_json::json!({
                 "type": "compiled",
-                "flow": source,
+                "flow": input,
                 "cpp": cpp,
                 "output": output,
                 "compiled": success,
                 "compilation_id": id,
+                "diagnostics": diagnostics,
             });
 
             if socket
@@ -144,19 +303,46 @@ async fn handle_ws(mut socket: WebSocket) {
     }
 }
 
+/// After `:undo` drops a statement, the session's cached stdout is stale —
+/// rerun what remains (or clear the cache if nothing does) so the next
+/// message's `new_output` diff is still correct.
+async fn refresh_session_stdout(session: &mut session::Session, config: &config::Config) {
+    if session.is_empty() {
+        session.set_last_stdout(String::new());
+        return;
+    }
+
+    let id = COMPILATIONS.fetch_add(1, Ordering::Relaxed) + 1;
+    let stdout = match transpiler::compile_program(session.program(), &config.compiler.allowed_includes) {
+        Ok(compiled) => match run_sandboxed(id, compiled.cpp, config).await {
+            RunOutcome::Success { stdout } => stdout,
+            _ => String::new(),
+        },
+        Err(_) => String::new(),
+    };
+    session.set_last_stdout(stdout);
+}
+
 #[tokio::main]
 async fn main() {
+    let config_path = PathBuf::from(
+        std::env::var("FLOW_CONFIG_PATH").unwrap_or_else(|_| "flow_chat.toml".to_string()),
+    );
+    let shared_config = Arc::new(SharedConfig::new(config::load_or_default(&config_path)));
+    config::spawn_config_watcher_system(config_path, shared_config.clone());
+
     let port: u16 = std::env::var("PORT")
         .ok()
         .and_then(|p| p.parse().ok())
-        .unwrap_or(9602);
+        .unwrap_or_else(|| shared_config.load().port);
 
     let app = Router::new()
         .route("/health", get(health))
         .route("/status", get(health))
         .route("/api/compile", post(compile_flow))
         .route("/ws", get(ws_handler))
-        .fallback_service(ServeDir::new("static"));
+        .fallback_service(ServeDir::new("static"))
+        .with_state(shared_config);
 
     println!("[flow-chat] Listening on 0.0.0.0:{port}");
     println!("[flow-chat] phi = {PHI}");
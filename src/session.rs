@@ -0,0 +1,112 @@
+//! Per-connection REPL session state for the WebSocket endpoint.
+//!
+//! `handle_ws` used to recompile every message as a standalone program, so a
+//! `define`d function from one message wasn't visible to the next. A
+//! `Session` instead accumulates top-level statements across messages into
+//! one growing [`Program`], so later messages are compiled against the full
+//! conversation so far.
+
+use crate::transpiler::Program;
+
+#[derive(Default)]
+pub struct Session {
+    program: Program,
+    last_stdout: String,
+}
+
+impl Session {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.program.stmts.is_empty()
+    }
+
+    pub fn program(&self) -> &Program {
+        &self.program
+    }
+
+    pub fn reset(&mut self) {
+        self.program = Program::default();
+        self.last_stdout.clear();
+    }
+
+    /// Drop the last top-level statement, if any. Returns whether anything
+    /// was removed.
+    pub fn undo(&mut self) -> bool {
+        self.program.stmts.pop().is_some()
+    }
+
+    /// The accumulated program with `fragment`'s statements appended,
+    /// without committing them — compiled and run first so a failing
+    /// fragment never enters the session.
+    pub fn with_fragment(&self, fragment: &Program) -> Program {
+        let mut stmts = self.program.stmts.clone();
+        stmts.extend(fragment.stmts.iter().cloned());
+        Program { stmts }
+    }
+
+    /// Record `program` (normally the result of [`with_fragment`]) as the
+    /// session's new state, along with the full stdout its run produced.
+    pub fn commit(&mut self, program: Program, stdout: String) {
+        self.program = program;
+        self.last_stdout = stdout;
+    }
+
+    pub fn set_last_stdout(&mut self, stdout: String) {
+        self.last_stdout = stdout;
+    }
+
+    /// The suffix of `full_stdout` produced by code added since the last
+    /// commit. Flow programs have no I/O or randomness, so re-running the
+    /// unchanged prefix of statements reproduces the same output prefix.
+    pub fn new_output<'a>(&self, full_stdout: &'a str) -> &'a str {
+        full_stdout.strip_prefix(self.last_stdout.as_str()).unwrap_or(full_stdout)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transpiler;
+
+    #[test]
+    fn test_new_output_diffs_against_last_commit() {
+        let mut session = Session::new();
+        session.set_last_stdout("1\n".to_string());
+        assert_eq!(session.new_output("1\n2\n"), "2\n");
+    }
+
+    #[test]
+    fn test_new_output_falls_back_to_full_stdout_when_prefix_mismatches() {
+        let mut session = Session::new();
+        session.set_last_stdout("1\n".to_string());
+        assert_eq!(session.new_output("hello\n"), "hello\n");
+    }
+
+    #[test]
+    fn test_undo_drops_last_statement() {
+        let mut session = Session::new();
+        let fragment = transpiler::parse("say 1\nsay 2").unwrap();
+        let combined = session.with_fragment(&fragment);
+        session.commit(combined, String::new());
+        assert_eq!(session.program().stmts.len(), 2);
+        assert!(session.undo());
+        assert_eq!(session.program().stmts.len(), 1);
+        assert!(session.undo());
+        assert!(session.is_empty());
+        assert!(!session.undo());
+    }
+
+    #[test]
+    fn test_reset_clears_program_and_stdout() {
+        let mut session = Session::new();
+        let fragment = transpiler::parse("say 1").unwrap();
+        let combined = session.with_fragment(&fragment);
+        session.commit(combined, "1\n".to_string());
+        session.reset();
+        assert!(session.is_empty());
+        assert_eq!(session.new_output("1\n"), "1\n");
+    }
+}
@@ -0,0 +1,121 @@
+//! AST node definitions shared by the parser and codegen passes.
+//!
+//! Every node carries a [`Span`] (line, col) pointing at the Flow token it
+//! originated from, so diagnostics can point back at the user's source
+//! instead of the generated C++.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub line: usize,
+    pub col: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Pow,
+    And,
+    Or,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Debug, Clone)]
+pub enum Expr {
+    Number(f64, Span),
+    Str(String, Span),
+    Bool(bool, Span),
+    Phi(Span),
+    Ident(String, Span),
+    Not(Box<Expr>, Span),
+    BinOp {
+        op: BinOp,
+        lhs: Box<Expr>,
+        rhs: Box<Expr>,
+        span: Span,
+    },
+    Call {
+        name: String,
+        args: Vec<Expr>,
+        span: Span,
+    },
+    /// A list literal, e.g. `[1, 2, 3]`. Lowered to a fixed-size `std::array`
+    /// in codegen; element-type and bounds checking happen in `semantics`.
+    List(Vec<Expr>, Span),
+    /// `list[index]`. Constant-index bounds checking happens in `semantics`.
+    Index {
+        list: Box<Expr>,
+        index: Box<Expr>,
+        span: Span,
+    },
+}
+
+impl Expr {
+    pub fn span(&self) -> Span {
+        match self {
+            Expr::Number(_, s)
+            | Expr::Str(_, s)
+            | Expr::Bool(_, s)
+            | Expr::Phi(s)
+            | Expr::Ident(_, s)
+            | Expr::Not(_, s)
+            | Expr::BinOp { span: s, .. }
+            | Expr::Call { span: s, .. }
+            | Expr::List(_, s)
+            | Expr::Index { span: s, .. } => *s,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Stmt {
+    Say(Expr, Span),
+    Let {
+        name: String,
+        value: Expr,
+        span: Span,
+    },
+    If {
+        cond: Expr,
+        then_body: Vec<Stmt>,
+        else_body: Vec<Stmt>,
+        span: Span,
+    },
+    Loop {
+        count: Expr,
+        body: Vec<Stmt>,
+        span: Span,
+    },
+    While {
+        cond: Expr,
+        body: Vec<Stmt>,
+        span: Span,
+    },
+    Define {
+        name: String,
+        params: Vec<String>,
+        body: Vec<Stmt>,
+        span: Span,
+    },
+    Return(Expr, Span),
+    Grow {
+        name: String,
+        span: Span,
+    },
+    Break(Span),
+    Continue(Span),
+    Expr(Expr, Span),
+}
+
+/// A fully parsed Flow source file: a flat sequence of top-level statements.
+#[derive(Debug, Clone, Default)]
+pub struct Program {
+    pub stmts: Vec<Stmt>,
+}
@@ -0,0 +1,250 @@
+//! Codegen pass: walks a [`Program`] and emits C++17 source.
+//!
+//! `define`d functions are hoisted above `main`; every other top-level
+//! statement becomes part of `main`'s body. Alongside the C++ text, this
+//! pass builds a [`SourceMapEntry`] for each line emitted from a Flow
+//! statement so g++ diagnostics can be mapped back to the Flow line that
+//! produced them. Closing braces and the boilerplate lines get no entry.
+
+use super::ast::{BinOp, Expr, Program, Span, Stmt};
+use super::diagnostics::SourceMapEntry;
+
+pub(crate) const PHI: &str = "1.6180339887498948";
+
+#[derive(Default)]
+struct IncludeSet {
+    iostream: bool,
+    string: bool,
+    cmath: bool,
+    array: bool,
+}
+
+#[derive(Debug)]
+pub struct CompileOutput {
+    pub cpp: String,
+    pub source_map: Vec<SourceMapEntry>,
+    /// Standard headers this program's C++ actually needs (e.g. `"array"`
+    /// for a list literal), so callers can check them against a config's
+    /// include allow-list without re-deriving them from the source text.
+    pub used_includes: Vec<&'static str>,
+}
+
+/// Accumulates emitted lines, recording the originating Flow span for each
+/// line added with `push` (lines added with `push_unmapped` have none).
+#[derive(Default)]
+struct Emitter {
+    lines: Vec<String>,
+    spans: Vec<(usize, Span)>, // (1-indexed line within `lines`, originating span)
+}
+
+impl Emitter {
+    fn push(&mut self, indent: usize, text: &str, span: Span) {
+        self.lines.push(format!("{}{text}", "    ".repeat(indent)));
+        self.spans.push((self.lines.len(), span));
+    }
+
+    fn push_unmapped(&mut self, indent: usize, text: &str) {
+        self.lines.push(format!("{}{text}", "    ".repeat(indent)));
+    }
+}
+
+pub fn codegen(program: &Program) -> CompileOutput {
+    let mut top = Emitter::default();
+    let mut body = Emitter::default();
+    let mut includes = IncludeSet::default();
+
+    for stmt in &program.stmts {
+        if let Stmt::Define { name, params, body: fn_body, span } = stmt {
+            let param_str = params
+                .iter()
+                .map(|p| format!("auto {p}"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            top.push(0, &format!("auto {name}({param_str}) {{"), *span);
+            emit_block(fn_body, 1, &mut top, &mut includes);
+            top.push_unmapped(0, "}");
+            top.push_unmapped(0, "");
+        } else {
+            emit_stmt(stmt, 1, &mut body, &mut includes);
+        }
+    }
+
+    let mut used_includes = Vec::new();
+    let mut cpp_lines: Vec<String> = Vec::new();
+    cpp_lines.push("// Generated by flowc — the Flow compiler".to_string());
+    if includes.iostream {
+        used_includes.push("iostream");
+        cpp_lines.push("#include <iostream>".into());
+    }
+    if includes.string {
+        used_includes.push("string");
+        cpp_lines.push("#include <string>".into());
+    }
+    if includes.cmath {
+        used_includes.push("cmath");
+        cpp_lines.push("#include <cmath>".into());
+    }
+    if includes.array {
+        used_includes.push("array");
+        cpp_lines.push("#include <array>".into());
+    }
+    cpp_lines.push(String::new());
+
+    let mut source_map = Vec::new();
+    let header_len = cpp_lines.len();
+    for (rel_line, span) in &top.spans {
+        source_map.push(SourceMapEntry {
+            cpp_line: header_len + rel_line,
+            flow_line: span.line,
+            flow_col: span.col,
+        });
+    }
+    cpp_lines.extend(top.lines);
+
+    cpp_lines.push("int main() {".into());
+    let body_base = cpp_lines.len();
+    for (rel_line, span) in &body.spans {
+        source_map.push(SourceMapEntry {
+            cpp_line: body_base + rel_line,
+            flow_line: span.line,
+            flow_col: span.col,
+        });
+    }
+    cpp_lines.extend(body.lines);
+    cpp_lines.push("    return 0;".into());
+    cpp_lines.push("}".into());
+
+    CompileOutput { cpp: cpp_lines.join("\n") + "\n", source_map, used_includes }
+}
+
+fn emit_block(stmts: &[Stmt], indent: usize, out: &mut Emitter, includes: &mut IncludeSet) {
+    for stmt in stmts {
+        emit_stmt(stmt, indent, out, includes);
+    }
+}
+
+fn emit_stmt(stmt: &Stmt, indent: usize, out: &mut Emitter, includes: &mut IncludeSet) {
+    match stmt {
+        Stmt::Say(expr, span) => {
+            includes.iostream = true;
+            let val = translate_expr(expr, includes);
+            out.push(indent, &format!("std::cout << {val} << std::endl;"), *span);
+        }
+        Stmt::Let { name, value, span } => {
+            let val = translate_expr(value, includes);
+            out.push(indent, &format!("auto {name} = {val};"), *span);
+        }
+        Stmt::If { cond, then_body, else_body, span } => {
+            let cond_cpp = translate_expr(cond, includes);
+            out.push(indent, &format!("if ({cond_cpp}) {{"), *span);
+            emit_block(then_body, indent + 1, out, includes);
+            if else_body.is_empty() {
+                out.push_unmapped(indent, "}");
+            } else {
+                out.push(indent, "} else {", *span);
+                emit_block(else_body, indent + 1, out, includes);
+                out.push_unmapped(indent, "}");
+            }
+        }
+        Stmt::Loop { count, body, span } => {
+            let n_cpp = translate_expr(count, includes);
+            out.push(indent, &format!("for (int _i = 0; _i < {n_cpp}; _i++) {{"), *span);
+            emit_block(body, indent + 1, out, includes);
+            out.push_unmapped(indent, "}");
+        }
+        Stmt::While { cond, body, span } => {
+            let cond_cpp = translate_expr(cond, includes);
+            out.push(indent, &format!("while ({cond_cpp}) {{"), *span);
+            emit_block(body, indent + 1, out, includes);
+            out.push_unmapped(indent, "}");
+        }
+        Stmt::Define { .. } => {
+            // Nested `define`s aren't supported; top-level handling in
+            // `codegen` hoists every Define it sees before reaching here.
+        }
+        Stmt::Return(expr, span) => {
+            let val = translate_expr(expr, includes);
+            out.push(indent, &format!("return {val};"), *span);
+        }
+        Stmt::Grow { name, span } => {
+            out.push(indent, &format!("{name} *= {PHI};"), *span);
+        }
+        Stmt::Break(span) => out.push(indent, "break;", *span),
+        Stmt::Continue(span) => out.push(indent, "continue;", *span),
+        Stmt::Expr(expr, span) => {
+            let val = translate_expr(expr, includes);
+            out.push(indent, &format!("{val};"), *span);
+        }
+    }
+}
+
+fn translate_expr(expr: &Expr, includes: &mut IncludeSet) -> String {
+    match expr {
+        Expr::Number(n, _) => format!("{n}"),
+        Expr::Str(s, _) => {
+            includes.string = true;
+            format!("std::string(\"{s}\")")
+        }
+        Expr::Bool(true, _) => "true".to_string(),
+        Expr::Bool(false, _) => "false".to_string(),
+        Expr::Phi(_) => PHI.to_string(),
+        Expr::Ident(name, _) => name.clone(),
+        Expr::Not(inner, _) => format!("!{}", translate_expr(inner, includes)),
+        Expr::Call { name, args, .. } => {
+            let arg_str = args
+                .iter()
+                .map(|a| translate_expr(a, includes))
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("{name}({arg_str})")
+        }
+        Expr::List(elements, _) => {
+            // `semantics::check` rejects mismatched element types before
+            // codegen runs, so C++17 class template argument deduction can
+            // infer a single element type here — except `semantics` treats
+            // whole numbers and fractions as the same `number` type, while
+            // C++ sees `int` vs `double`. If any element is fractional,
+            // force every whole-number literal to print with a decimal
+            // point too, so CTAD still sees one uniform type.
+            includes.array = true;
+            let any_fractional =
+                elements.iter().any(|e| matches!(e, Expr::Number(n, _) if n.fract() != 0.0));
+            let items = elements
+                .iter()
+                .map(|e| match e {
+                    Expr::Number(n, _) if any_fractional && n.fract() == 0.0 => format!("{n:.1}"),
+                    _ => translate_expr(e, includes),
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("std::array{{{items}}}")
+        }
+        Expr::Index { list, index, .. } => {
+            let l = translate_expr(list, includes);
+            let i = translate_expr(index, includes);
+            format!("{l}[{i}]")
+        }
+        Expr::BinOp { op, lhs, rhs, .. } => {
+            let l = translate_expr(lhs, includes);
+            let r = translate_expr(rhs, includes);
+            match op {
+                BinOp::Pow => {
+                    includes.cmath = true;
+                    format!("std::pow({l}, {r})")
+                }
+                BinOp::Add => format!("({l} + {r})"),
+                BinOp::Sub => format!("({l} - {r})"),
+                BinOp::Mul => format!("({l} * {r})"),
+                BinOp::Div => format!("({l} / {r})"),
+                BinOp::And => format!("({l} && {r})"),
+                BinOp::Or => format!("({l} || {r})"),
+                BinOp::Eq => format!("({l} == {r})"),
+                BinOp::Ne => format!("({l} != {r})"),
+                BinOp::Lt => format!("({l} < {r})"),
+                BinOp::Le => format!("({l} <= {r})"),
+                BinOp::Gt => format!("({l} > {r})"),
+                BinOp::Ge => format!("({l} >= {r})"),
+            }
+        }
+    }
+}
@@ -0,0 +1,96 @@
+//! Diagnostics surfaced to API/WS clients, and the Flow<->C++ source map
+//! codegen builds to make them possible.
+//!
+//! Parse errors, semantic-check errors and the include allow-list check are
+//! already Flow-located when they become a `Diagnostic`; g++ stderr is
+//! located in generated C++ and mapped back here via `source_map`.
+
+use super::ast::Span;
+use super::parser::ParseError;
+use serde::Serialize;
+
+/// Associates one emitted C++ line with the Flow (line, col) that produced it.
+#[derive(Debug, Clone, Copy)]
+pub struct SourceMapEntry {
+    pub cpp_line: usize,
+    pub flow_line: usize,
+    pub flow_col: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Diagnostic {
+    pub flow_line: usize,
+    pub flow_col: usize,
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl Diagnostic {
+    fn from_span(span: Span, severity: Severity, message: String) -> Self {
+        Diagnostic { flow_line: span.line, flow_col: span.col, severity, message }
+    }
+}
+
+pub fn parse_errors_to_diagnostics(errors: &[ParseError]) -> Vec<Diagnostic> {
+    errors
+        .iter()
+        .map(|e| Diagnostic::from_span(e.span, Severity::Error, e.message.clone()))
+        .collect()
+}
+
+/// Parse g++'s `path:LINE:COL: severity: message` stderr lines and map each
+/// one back to the Flow source line/col that produced the offending C++.
+/// Lines that don't match the pattern (continuation lines, summaries) are
+/// dropped — g++'s own column is within the generated C++ and isn't
+/// meaningful once mapped back to Flow, so only the line is resolved.
+pub fn diagnostics_from_gpp_stderr(stderr: &str, source_map: &[SourceMapEntry]) -> Vec<Diagnostic> {
+    stderr
+        .lines()
+        .filter_map(parse_gpp_line)
+        .map(|raw| {
+            let (flow_line, flow_col) = resolve(raw.cpp_line, source_map);
+            Diagnostic { flow_line, flow_col, severity: raw.severity, message: raw.message }
+        })
+        .collect()
+}
+
+struct RawDiag {
+    cpp_line: usize,
+    severity: Severity,
+    message: String,
+}
+
+fn parse_gpp_line(line: &str) -> Option<RawDiag> {
+    let mut parts = line.splitn(4, ':');
+    let _path = parts.next()?;
+    let cpp_line: usize = parts.next()?.trim().parse().ok()?;
+    let _cpp_col: usize = parts.next()?.trim().parse().ok()?;
+    let rest = parts.next()?.trim();
+
+    let (severity, message) = if let Some(msg) = rest.strip_prefix("error:") {
+        (Severity::Error, msg.trim().to_string())
+    } else if let Some(msg) = rest.strip_prefix("warning:") {
+        (Severity::Warning, msg.trim().to_string())
+    } else {
+        return None;
+    };
+
+    Some(RawDiag { cpp_line, severity, message })
+}
+
+fn resolve(cpp_line: usize, source_map: &[SourceMapEntry]) -> (usize, usize) {
+    source_map
+        .iter()
+        .filter(|e| e.cpp_line <= cpp_line)
+        .max_by_key(|e| e.cpp_line)
+        .or_else(|| source_map.first())
+        .map(|e| (e.flow_line, e.flow_col))
+        .unwrap_or((1, 1))
+}
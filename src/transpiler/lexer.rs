@@ -0,0 +1,151 @@
+//! Tokenizer for Flow source. Produces a flat token stream with spans;
+//! `--` line comments and whitespace are dropped as trivia.
+
+use super::ast::Span;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenKind {
+    Ident(String),
+    Number(f64),
+    Str(String),
+    Symbol(&'static str),
+    Eof,
+}
+
+#[derive(Debug, Clone)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub span: Span,
+}
+
+/// A lexical error (so far, just an unterminated string literal), keyed to
+/// the span where it started. Merged into the parser's own errors by
+/// [`super::parser::parse`], which is why the shape mirrors `ParseError`.
+#[derive(Debug, Clone)]
+pub struct LexError {
+    pub message: String,
+    pub span: Span,
+}
+
+pub fn lex(source: &str) -> (Vec<Token>, Vec<LexError>) {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut errors = Vec::new();
+    let mut i = 0usize;
+    let mut line = 1usize;
+    let mut col = 1usize;
+
+    macro_rules! bump {
+        () => {{
+            if chars[i] == '\n' {
+                line += 1;
+                col = 1;
+            } else {
+                col += 1;
+            }
+            i += 1;
+        }};
+    }
+
+    while i < chars.len() {
+        let c = chars[i];
+
+        if c == '-' && i + 1 < chars.len() && chars[i + 1] == '-' {
+            while i < chars.len() && chars[i] != '\n' {
+                bump!();
+            }
+            continue;
+        }
+
+        if c.is_whitespace() {
+            bump!();
+            continue;
+        }
+
+        let start = Span { line, col };
+
+        if c == '"' {
+            bump!();
+            let mut s = String::new();
+            while i < chars.len() && chars[i] != '"' {
+                s.push(chars[i]);
+                bump!();
+            }
+            if i < chars.len() {
+                bump!(); // closing quote
+            } else {
+                errors.push(LexError { message: "unterminated string literal".to_string(), span: start });
+            }
+            tokens.push(Token { kind: TokenKind::Str(s), span: start });
+            continue;
+        }
+
+        if c.is_ascii_digit() {
+            let mut s = String::new();
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                s.push(chars[i]);
+                bump!();
+            }
+            tokens.push(Token {
+                kind: TokenKind::Number(s.parse().unwrap_or(0.0)),
+                span: start,
+            });
+            continue;
+        }
+
+        if c.is_alphabetic() || c == '_' {
+            let mut s = String::new();
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                s.push(chars[i]);
+                bump!();
+            }
+            tokens.push(Token { kind: TokenKind::Ident(s), span: start });
+            continue;
+        }
+
+        let two: Option<&'static str> = if i + 1 < chars.len() {
+            match (c, chars[i + 1]) {
+                ('=', '=') => Some("=="),
+                ('!', '=') => Some("!="),
+                ('<', '=') => Some("<="),
+                ('>', '=') => Some(">="),
+                _ => None,
+            }
+        } else {
+            None
+        };
+
+        if let Some(sym) = two {
+            bump!();
+            bump!();
+            tokens.push(Token { kind: TokenKind::Symbol(sym), span: start });
+            continue;
+        }
+
+        let one: &'static str = match c {
+            '=' => "=",
+            '+' => "+",
+            '-' => "-",
+            '*' => "*",
+            '/' => "/",
+            '^' => "^",
+            '(' => "(",
+            ')' => ")",
+            '[' => "[",
+            ']' => "]",
+            ',' => ",",
+            '<' => "<",
+            '>' => ">",
+            '!' => "!",
+            _ => {
+                bump!();
+                continue;
+            }
+        };
+        bump!();
+        tokens.push(Token { kind: TokenKind::Symbol(one), span: start });
+    }
+
+    tokens.push(Token { kind: TokenKind::Eof, span: Span { line, col } });
+    (tokens, errors)
+}
@@ -0,0 +1,244 @@
+//! Flow-to-C++ transpiler.
+//!
+//! Source goes through four stages: [`lexer::lex`] tokenizes it,
+//! [`parser::parse`] builds an AST ([`ast::Program`]) via recursive
+//! descent, [`semantics::check`] rejects programs with type or bounds
+//! errors, and [`codegen::codegen`] walks the (now-valid) AST to emit
+//! C++17. Each AST node keeps the (line, col) span of the Flow token it
+//! came from, so every stage can report errors against the original source
+//! rather than the generated code.
+
+mod ast;
+mod codegen;
+mod diagnostics;
+mod lexer;
+mod parser;
+mod semantics;
+
+pub use ast::Program;
+pub use codegen::CompileOutput;
+pub use diagnostics::{diagnostics_from_gpp_stderr, Diagnostic, SourceMapEntry};
+use codegen::codegen;
+use diagnostics::Severity;
+
+/// Parse `source` into an AST without semantic-checking or codegen'ing it.
+/// Exposed so the WebSocket REPL session can merge a new message's fragment
+/// into its accumulated [`Program`] before compiling the whole thing;
+/// one-shot callers should use [`compile`] instead.
+pub fn parse(source: &str) -> Result<Program, Vec<Diagnostic>> {
+    parser::parse(source).map_err(|errors| diagnostics::parse_errors_to_diagnostics(&errors))
+}
+
+/// Semantic-check and codegen an already-parsed [`Program`]. Shared by
+/// [`compile`] and by the REPL session, which builds its `Program` by
+/// concatenating fragments across messages rather than parsing one string.
+pub fn compile_program(program: &Program, allowed_includes: &[String]) -> Result<CompileOutput, Vec<Diagnostic>> {
+    let semantic_errors = semantics::check(program);
+    if !semantic_errors.is_empty() {
+        return Err(semantic_errors);
+    }
+
+    let output = codegen(program);
+    let disallowed: Vec<&str> = output
+        .used_includes
+        .iter()
+        .filter(|inc| !allowed_includes.iter().any(|allowed| allowed == *inc))
+        .copied()
+        .collect();
+    if !disallowed.is_empty() {
+        return Err(vec![Diagnostic {
+            flow_line: 1,
+            flow_col: 1,
+            severity: Severity::Error,
+            message: format!(
+                "DisallowedInclude: this program requires <{}>, which isn't in the configured allow-list",
+                disallowed.join(">, <")
+            ),
+        }]);
+    }
+
+    Ok(output)
+}
+
+/// Run the full pipeline — parse, semantic check, codegen, then an
+/// include-allow-list check — returning the C++ output and its source map,
+/// or diagnostics if any stage rejected the source. `allowed_includes`
+/// comes from the live [`crate::config::Config`] so operators can loosen or
+/// tighten it without a restart.
+pub fn compile(source: &str, allowed_includes: &[String]) -> Result<CompileOutput, Vec<Diagnostic>> {
+    let program = parse(source)?;
+    compile_program(&program, allowed_includes)
+}
+
+/// Transpile Flow source to C++17. Errors from any stage (parse, semantic
+/// check, include allow-list) are reported inline as C++ comments rather
+/// than failing outright, since callers currently expect a `String` back;
+/// richer diagnostics are exposed separately via [`compile`].
+#[allow(dead_code)] // kept as the stable one-shot entry point; the server uses `compile` for diagnostics
+pub fn transpile(source: &str, allowed_includes: &[String]) -> String {
+    match compile(source, allowed_includes) {
+        Ok(out) => out.cpp,
+        Err(diags) => {
+            let mut lines: Vec<String> = diags
+                .iter()
+                .map(|d| format!("// error at {}:{}: {}", d.flow_line, d.flow_col, d.message))
+                .collect();
+            lines.push(String::new());
+            lines.join("\n")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every standard header codegen can emit, for tests that don't care
+    /// about the include allow-list itself.
+    fn all_includes() -> Vec<String> {
+        ["iostream", "string", "cmath", "array"].iter().map(|s| s.to_string()).collect()
+    }
+
+    fn transpile(source: &str) -> String {
+        super::transpile(source, &all_includes())
+    }
+
+    fn compile(source: &str) -> Result<CompileOutput, Vec<Diagnostic>> {
+        super::compile(source, &all_includes())
+    }
+
+    #[test]
+    fn test_say() {
+        let out = transpile("say \"hello\"");
+        assert!(out.contains("std::cout"));
+        assert!(out.contains("hello"));
+    }
+
+    #[test]
+    fn test_let() {
+        let out = transpile("let x = 42");
+        assert!(out.contains("auto x = 42;"));
+    }
+
+    #[test]
+    fn test_phi() {
+        let out = transpile("say phi");
+        assert!(out.contains(codegen::PHI));
+    }
+
+    #[test]
+    fn test_nested_if() {
+        let out = transpile(
+            "if true then\n  if false then\n    say 1\n  else\n    say 2\n  end\nend",
+        );
+        assert!(out.contains("if (true) {"));
+        assert!(out.contains("if (false) {"));
+    }
+
+    #[test]
+    fn test_disallowed_include_rejected() {
+        let diags = super::compile("let nums = [1, 2, 3]", &[]).unwrap_err();
+        assert_eq!(diags.len(), 1);
+        assert!(diags[0].message.contains("DisallowedInclude"));
+        assert!(diags[0].message.contains("array"));
+    }
+
+    #[test]
+    fn test_gpp_diagnostics_map_to_flow_line() {
+        let compiled = compile("let x = 1\nsay undeclared_var").unwrap();
+        let say_entry = compiled
+            .source_map
+            .iter()
+            .find(|e| compiled.cpp.lines().nth(e.cpp_line - 1).unwrap().contains("undeclared_var"))
+            .unwrap();
+
+        let stderr = format!(
+            "flow_chat_tmp.cpp:{}:5: error: 'undeclared_var' was not declared in this scope",
+            say_entry.cpp_line
+        );
+        let diags = diagnostics_from_gpp_stderr(&stderr, &compiled.source_map);
+
+        assert_eq!(diags.len(), 1);
+        assert_eq!(diags[0].flow_line, 2);
+        assert!(diags[0].message.contains("undeclared_var"));
+    }
+
+    #[test]
+    fn test_list_literal_and_index() {
+        let out = transpile("let nums = [1, 2, 3]\nsay nums[1]");
+        assert!(out.contains("#include <array>"));
+        assert!(out.contains("auto nums = std::array{1, 2, 3};"));
+        assert!(out.contains("std::cout << nums[1] << std::endl;"));
+    }
+
+    #[test]
+    fn test_pushing_invalid_type_rejected() {
+        let diags = compile("let bad = [1, false]").unwrap_err();
+        assert_eq!(diags.len(), 1);
+        assert!(diags[0].message.contains("PushingInvalidType"));
+    }
+
+    #[test]
+    fn test_index_out_of_range_rejected() {
+        let diags = compile("let nums = [1, 2, 3]\nsay nums[5]").unwrap_err();
+        assert_eq!(diags.len(), 1);
+        assert!(diags[0].message.contains("IndexOutOfRange"));
+    }
+
+    #[test]
+    fn test_mixed_int_and_fraction_list_uses_uniform_double_type() {
+        let out = transpile("let nums = [1, 2.5]");
+        assert!(out.contains("std::array{1.0, 2.5}"));
+    }
+
+    #[test]
+    fn test_define_param_scoped_separately_from_outer_list() {
+        // `nums` inside `f` is f's own unconstrained parameter, not the
+        // 3-element list `nums` declared outside — indexing it at 5 must
+        // not be flagged as out of range.
+        let result = compile("let nums = [1, 2, 3]\ndefine f(nums)\n  say nums[5]\nend");
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_let_inside_if_does_not_leak_into_parent_scope() {
+        // `nums` declared inside the `if` is block-scoped in the generated
+        // C++, so indexing an outer `nums` of a different size afterward
+        // must use the outer list's bounds, not the inner one's.
+        let result = compile(
+            "let nums = [1, 2, 3, 4, 5]\nif true then\n  let nums = [1, 2]\nend\nsay nums[4]",
+        );
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_nested_define_rejected() {
+        let diags = compile("if true then\n  define f()\n    return 1\n  end\nend\nsay f()").unwrap_err();
+        assert_eq!(diags.len(), 1);
+        assert!(diags[0].message.contains("NestedDefine"));
+    }
+
+    #[test]
+    fn test_unterminated_string_literal_rejected() {
+        let diags = super::compile("say \"hello\nlet x = 1", &all_includes()).unwrap_err();
+        assert_eq!(diags.len(), 1);
+        assert!(diags[0].message.contains("unterminated string literal"));
+    }
+
+    #[test]
+    fn test_operator_precedence() {
+        let out = transpile("say 1 + 2 * 3");
+        assert!(out.contains("(1 + (2 * 3))"));
+
+        let out = transpile("say 2 ^ 3 ^ 2");
+        assert!(out.contains("std::pow(2, std::pow(3, 2))"));
+    }
+
+    #[test]
+    fn test_define_and_call() {
+        let out = transpile("define square(x)\n  return x * x\nend\nsay square(5)");
+        assert!(out.contains("auto square(auto x) {"));
+        assert!(out.contains("return (x * x);"));
+        assert!(out.contains("std::cout << square(5) << std::endl;"));
+    }
+}
@@ -0,0 +1,419 @@
+//! Recursive-descent parser: turns the token stream into a [`Program`].
+//!
+//! Errors are collected rather than aborting on the first one, so a single
+//! `parse` call can report every malformed statement in a source file.
+
+use super::ast::{BinOp, Expr, Program, Span, Stmt};
+use super::lexer::{lex, Token, TokenKind};
+
+#[derive(Debug, Clone)]
+pub struct ParseError {
+    pub message: String,
+    pub span: Span,
+}
+
+pub fn parse(source: &str) -> Result<Program, Vec<ParseError>> {
+    let (tokens, lex_errors) = lex(source);
+    let mut parser = Parser::new(tokens);
+    let mut stmts = Vec::new();
+    let mut errors: Vec<ParseError> =
+        lex_errors.into_iter().map(|e| ParseError { message: e.message, span: e.span }).collect();
+
+    while !parser.at_eof() {
+        match parser.parse_stmt() {
+            Ok(stmt) => stmts.push(stmt),
+            Err(message) => {
+                errors.push(ParseError { message, span: parser.span() });
+                parser.advance(); // skip the offending token and keep going
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(Program { stmts })
+    } else {
+        Err(errors)
+    }
+}
+
+const BLOCK_ENDERS: &[&str] = &["end", "else"];
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn new(tokens: Vec<Token>) -> Self {
+        Self { tokens, pos: 0 }
+    }
+
+    fn peek(&self) -> &Token {
+        &self.tokens[self.pos]
+    }
+
+    fn span(&self) -> Span {
+        self.peek().span
+    }
+
+    fn at_eof(&self) -> bool {
+        matches!(self.peek().kind, TokenKind::Eof)
+    }
+
+    fn advance(&mut self) -> Token {
+        let tok = self.tokens[self.pos].clone();
+        if self.pos + 1 < self.tokens.len() {
+            self.pos += 1;
+        }
+        tok
+    }
+
+    fn is_ident(&self, word: &str) -> bool {
+        matches!(&self.peek().kind, TokenKind::Ident(s) if s == word)
+    }
+
+    fn is_symbol(&self, sym: &str) -> bool {
+        matches!(&self.peek().kind, TokenKind::Symbol(s) if *s == sym)
+    }
+
+    fn eat_ident(&mut self, word: &str) -> Result<Span, String> {
+        if self.is_ident(word) {
+            let span = self.span();
+            self.advance();
+            Ok(span)
+        } else {
+            Err(format!("expected '{word}'"))
+        }
+    }
+
+    fn eat_symbol(&mut self, sym: &str) -> Result<Span, String> {
+        if self.is_symbol(sym) {
+            let span = self.span();
+            self.advance();
+            Ok(span)
+        } else {
+            Err(format!("expected '{sym}'"))
+        }
+    }
+
+    fn expect_ident_name(&mut self) -> Result<(String, Span), String> {
+        let span = self.span();
+        match self.advance().kind {
+            TokenKind::Ident(name) => Ok((name, span)),
+            _ => Err("expected an identifier".to_string()),
+        }
+    }
+
+    fn at_block_end(&self) -> bool {
+        if self.at_eof() {
+            return true;
+        }
+        BLOCK_ENDERS.iter().any(|w| self.is_ident(w))
+    }
+
+    fn parse_block(&mut self) -> Result<Vec<Stmt>, String> {
+        let mut stmts = Vec::new();
+        while !self.at_block_end() {
+            stmts.push(self.parse_stmt()?);
+        }
+        Ok(stmts)
+    }
+
+    fn parse_stmt(&mut self) -> Result<Stmt, String> {
+        let span = self.span();
+
+        if self.is_ident("say") {
+            self.advance();
+            let expr = self.parse_expr()?;
+            return Ok(Stmt::Say(expr, span));
+        }
+
+        if self.is_ident("let") {
+            self.advance();
+            let (name, _) = self.expect_ident_name()?;
+            self.eat_symbol("=")?;
+            let value = self.parse_expr()?;
+            return Ok(Stmt::Let { name, value, span });
+        }
+
+        if self.is_ident("if") {
+            self.advance();
+            let cond = self.parse_expr()?;
+            self.eat_ident("then")?;
+            let then_body = self.parse_block()?;
+            let else_body = if self.is_ident("else") {
+                self.advance();
+                self.parse_block()?
+            } else {
+                Vec::new()
+            };
+            self.eat_ident("end")?;
+            return Ok(Stmt::If { cond, then_body, else_body, span });
+        }
+
+        if self.is_ident("loop") {
+            self.advance();
+            let count = self.parse_expr()?;
+            self.eat_ident("times")?;
+            let body = self.parse_block()?;
+            self.eat_ident("end")?;
+            return Ok(Stmt::Loop { count, body, span });
+        }
+
+        if self.is_ident("while") {
+            self.advance();
+            let cond = self.parse_expr()?;
+            self.eat_ident("do")?;
+            let body = self.parse_block()?;
+            self.eat_ident("end")?;
+            return Ok(Stmt::While { cond, body, span });
+        }
+
+        if self.is_ident("define") {
+            self.advance();
+            let (name, _) = self.expect_ident_name()?;
+            self.eat_symbol("(")?;
+            let mut params = Vec::new();
+            if !self.is_symbol(")") {
+                loop {
+                    let (param, _) = self.expect_ident_name()?;
+                    params.push(param);
+                    if self.is_symbol(",") {
+                        self.advance();
+                        continue;
+                    }
+                    break;
+                }
+            }
+            self.eat_symbol(")")?;
+            let body = self.parse_block()?;
+            self.eat_ident("end")?;
+            return Ok(Stmt::Define { name, params, body, span });
+        }
+
+        if self.is_ident("return") {
+            self.advance();
+            let expr = self.parse_expr()?;
+            return Ok(Stmt::Return(expr, span));
+        }
+
+        if self.is_ident("grow") {
+            self.advance();
+            let (name, _) = self.expect_ident_name()?;
+            return Ok(Stmt::Grow { name, span });
+        }
+
+        if self.is_ident("break") {
+            self.advance();
+            return Ok(Stmt::Break(span));
+        }
+
+        if self.is_ident("continue") {
+            self.advance();
+            return Ok(Stmt::Continue(span));
+        }
+
+        let expr = self.parse_expr()?;
+        Ok(Stmt::Expr(expr, span))
+    }
+
+    // Precedence, low to high: or, and, equality, comparison, additive,
+    // multiplicative, power (right-assoc), unary not, primary.
+
+    fn parse_expr(&mut self) -> Result<Expr, String> {
+        self.parse_or()
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.parse_and()?;
+        while self.is_ident("or") {
+            let span = self.span();
+            self.advance();
+            let rhs = self.parse_and()?;
+            lhs = Expr::BinOp { op: BinOp::Or, lhs: Box::new(lhs), rhs: Box::new(rhs), span };
+        }
+        Ok(lhs)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.parse_equality()?;
+        while self.is_ident("and") {
+            let span = self.span();
+            self.advance();
+            let rhs = self.parse_equality()?;
+            lhs = Expr::BinOp { op: BinOp::And, lhs: Box::new(lhs), rhs: Box::new(rhs), span };
+        }
+        Ok(lhs)
+    }
+
+    fn parse_equality(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.parse_comparison()?;
+        loop {
+            let op = if self.is_symbol("==") {
+                BinOp::Eq
+            } else if self.is_symbol("!=") {
+                BinOp::Ne
+            } else {
+                break;
+            };
+            let span = self.span();
+            self.advance();
+            let rhs = self.parse_comparison()?;
+            lhs = Expr::BinOp { op, lhs: Box::new(lhs), rhs: Box::new(rhs), span };
+        }
+        Ok(lhs)
+    }
+
+    fn parse_comparison(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.parse_additive()?;
+        loop {
+            let op = if self.is_symbol("<") {
+                BinOp::Lt
+            } else if self.is_symbol("<=") {
+                BinOp::Le
+            } else if self.is_symbol(">") {
+                BinOp::Gt
+            } else if self.is_symbol(">=") {
+                BinOp::Ge
+            } else {
+                break;
+            };
+            let span = self.span();
+            self.advance();
+            let rhs = self.parse_additive()?;
+            lhs = Expr::BinOp { op, lhs: Box::new(lhs), rhs: Box::new(rhs), span };
+        }
+        Ok(lhs)
+    }
+
+    fn parse_additive(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.parse_mul()?;
+        loop {
+            let op = if self.is_symbol("+") {
+                BinOp::Add
+            } else if self.is_symbol("-") {
+                BinOp::Sub
+            } else {
+                break;
+            };
+            let span = self.span();
+            self.advance();
+            let rhs = self.parse_mul()?;
+            lhs = Expr::BinOp { op, lhs: Box::new(lhs), rhs: Box::new(rhs), span };
+        }
+        Ok(lhs)
+    }
+
+    fn parse_mul(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.parse_pow()?;
+        loop {
+            let op = if self.is_symbol("*") {
+                BinOp::Mul
+            } else if self.is_symbol("/") {
+                BinOp::Div
+            } else {
+                break;
+            };
+            let span = self.span();
+            self.advance();
+            let rhs = self.parse_pow()?;
+            lhs = Expr::BinOp { op, lhs: Box::new(lhs), rhs: Box::new(rhs), span };
+        }
+        Ok(lhs)
+    }
+
+    fn parse_pow(&mut self) -> Result<Expr, String> {
+        let lhs = self.parse_unary()?;
+        if self.is_symbol("^") {
+            let span = self.span();
+            self.advance();
+            let rhs = self.parse_pow()?; // right-associative
+            return Ok(Expr::BinOp { op: BinOp::Pow, lhs: Box::new(lhs), rhs: Box::new(rhs), span });
+        }
+        Ok(lhs)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr, String> {
+        if self.is_ident("not") {
+            let span = self.span();
+            self.advance();
+            let expr = self.parse_unary()?;
+            return Ok(Expr::Not(Box::new(expr), span));
+        }
+        self.parse_postfix()
+    }
+
+    fn parse_postfix(&mut self) -> Result<Expr, String> {
+        let mut expr = self.parse_primary()?;
+        while self.is_symbol("[") {
+            let span = self.span();
+            self.advance();
+            let index = self.parse_expr()?;
+            self.eat_symbol("]")?;
+            expr = Expr::Index { list: Box::new(expr), index: Box::new(index), span };
+        }
+        Ok(expr)
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr, String> {
+        let span = self.span();
+        match self.peek().kind.clone() {
+            TokenKind::Number(n) => {
+                self.advance();
+                Ok(Expr::Number(n, span))
+            }
+            TokenKind::Str(s) => {
+                self.advance();
+                Ok(Expr::Str(s, span))
+            }
+            TokenKind::Ident(name) => {
+                self.advance();
+                match name.as_str() {
+                    "true" => Ok(Expr::Bool(true, span)),
+                    "false" => Ok(Expr::Bool(false, span)),
+                    "phi" => Ok(Expr::Phi(span)),
+                    _ if self.is_symbol("(") => {
+                        self.advance();
+                        let mut args = Vec::new();
+                        if !self.is_symbol(")") {
+                            loop {
+                                args.push(self.parse_expr()?);
+                                if self.is_symbol(",") {
+                                    self.advance();
+                                    continue;
+                                }
+                                break;
+                            }
+                        }
+                        self.eat_symbol(")")?;
+                        Ok(Expr::Call { name, args, span })
+                    }
+                    _ => Ok(Expr::Ident(name, span)),
+                }
+            }
+            TokenKind::Symbol("(") => {
+                self.advance();
+                let inner = self.parse_expr()?;
+                self.eat_symbol(")")?;
+                Ok(inner)
+            }
+            TokenKind::Symbol("[") => {
+                self.advance();
+                let mut elements = Vec::new();
+                if !self.is_symbol("]") {
+                    loop {
+                        elements.push(self.parse_expr()?);
+                        if self.is_symbol(",") {
+                            self.advance();
+                            continue;
+                        }
+                        break;
+                    }
+                }
+                self.eat_symbol("]")?;
+                Ok(Expr::List(elements, span))
+            }
+            _ => Err("expected an expression".to_string()),
+        }
+    }
+}
@@ -0,0 +1,180 @@
+//! Semantic checks run on the AST between parsing and codegen.
+//!
+//! Today this catches the two classes of error that would otherwise surface
+//! as cryptic C++ template errors once a list reaches g++: pushing a
+//! mismatched element type into a list literal (`PushingInvalidType`), and
+//! indexing a list with a constant that's out of bounds (`IndexOutOfRange`).
+//! It also rejects a `define` nested inside a block (`NestedDefine`), which
+//! codegen would otherwise drop. All are reported as ordinary
+//! [`Diagnostic`]s with a Flow source span.
+
+use std::collections::HashMap;
+
+use super::ast::{Expr, Program, Stmt};
+use super::diagnostics::{Diagnostic, Severity};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ValueType {
+    Number,
+    Str,
+    Bool,
+    Unknown,
+}
+
+impl ValueType {
+    fn name(self) -> &'static str {
+        match self {
+            ValueType::Number => "number",
+            ValueType::Str => "string",
+            ValueType::Bool => "bool",
+            ValueType::Unknown => "unknown",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum ExprType {
+    Scalar(ValueType),
+    List { elem: ValueType, size: usize },
+}
+
+/// Per-name bindings inferred from `let`, used to resolve list size/element
+/// type when a variable (rather than a literal) is indexed later on.
+type Env = HashMap<String, ExprType>;
+
+pub fn check(program: &Program) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let mut env = Env::new();
+    check_block(&program.stmts, &mut env, true, &mut diagnostics);
+    diagnostics
+}
+
+fn check_block(stmts: &[Stmt], env: &mut Env, top_level: bool, diagnostics: &mut Vec<Diagnostic>) {
+    for stmt in stmts {
+        check_stmt(stmt, env, top_level, diagnostics);
+    }
+}
+
+/// Check a nested block (an `if`/`loop`/`while` body) in a scope of its own,
+/// so a `let` declared inside it doesn't leak into the parent — matching the
+/// C++ block scoping codegen gives it.
+fn check_child_block(stmts: &[Stmt], env: &Env, diagnostics: &mut Vec<Diagnostic>) {
+    let mut child = env.clone();
+    check_block(stmts, &mut child, false, diagnostics);
+}
+
+fn check_stmt(stmt: &Stmt, env: &mut Env, top_level: bool, diagnostics: &mut Vec<Diagnostic>) {
+    match stmt {
+        Stmt::Say(expr, _) | Stmt::Return(expr, _) | Stmt::Expr(expr, _) => {
+            check_expr(expr, env, diagnostics);
+        }
+        Stmt::Let { name, value, .. } => {
+            let ty = check_expr(value, env, diagnostics);
+            env.insert(name.clone(), ty);
+        }
+        Stmt::If { cond, then_body, else_body, .. } => {
+            check_expr(cond, env, diagnostics);
+            check_child_block(then_body, env, diagnostics);
+            check_child_block(else_body, env, diagnostics);
+        }
+        Stmt::Loop { count, body, .. } => {
+            check_expr(count, env, diagnostics);
+            check_child_block(body, env, diagnostics);
+        }
+        Stmt::While { cond, body, .. } => {
+            check_expr(cond, env, diagnostics);
+            check_child_block(body, env, diagnostics);
+        }
+        Stmt::Define { params, body, span, .. } => {
+            // Codegen only hoists `define`s it finds as direct children of
+            // `Program::stmts`; one nested inside an `if`/`loop`/`while`
+            // would silently vanish from the emitted C++ while the calls to
+            // it survive, so reject it here instead of letting that happen.
+            if !top_level {
+                diagnostics.push(Diagnostic {
+                    flow_line: span.line,
+                    flow_col: span.col,
+                    severity: Severity::Error,
+                    message: "NestedDefine: functions can only be defined at the top level".to_string(),
+                });
+                return;
+            }
+            // Codegen turns a `define` into a standalone C++ function, with
+            // no access to the caller's locals and parameters of unknown
+            // (generic `auto`) type — so it gets a fresh scope seeded only
+            // with its own parameters, rather than inheriting `env`.
+            let mut fn_env: Env =
+                params.iter().map(|p| (p.clone(), ExprType::Scalar(ValueType::Unknown))).collect();
+            check_block(body, &mut fn_env, false, diagnostics);
+        }
+        Stmt::Grow { .. } | Stmt::Break(_) | Stmt::Continue(_) => {}
+    }
+}
+
+fn check_expr(expr: &Expr, env: &mut Env, diagnostics: &mut Vec<Diagnostic>) -> ExprType {
+    match expr {
+        Expr::Number(_, _) | Expr::Phi(_) => ExprType::Scalar(ValueType::Number),
+        Expr::Str(_, _) => ExprType::Scalar(ValueType::Str),
+        Expr::Bool(_, _) | Expr::Not(_, _) => ExprType::Scalar(ValueType::Bool),
+        Expr::Ident(name, _) => env.get(name).copied().unwrap_or(ExprType::Scalar(ValueType::Unknown)),
+        Expr::BinOp { lhs, rhs, .. } => {
+            check_expr(lhs, env, diagnostics);
+            check_expr(rhs, env, diagnostics);
+            ExprType::Scalar(ValueType::Unknown)
+        }
+        Expr::Call { args, .. } => {
+            for arg in args {
+                check_expr(arg, env, diagnostics);
+            }
+            ExprType::Scalar(ValueType::Unknown)
+        }
+        Expr::List(elements, _) => {
+            let mut expected: Option<ValueType> = None;
+            for element in elements {
+                let elem_ty = match check_expr(element, env, diagnostics) {
+                    ExprType::Scalar(t) => t,
+                    ExprType::List { .. } => ValueType::Unknown,
+                };
+                if elem_ty == ValueType::Unknown {
+                    continue;
+                }
+                match expected {
+                    None => expected = Some(elem_ty),
+                    Some(want) if want != elem_ty => {
+                        diagnostics.push(Diagnostic {
+                            flow_line: element.span().line,
+                            flow_col: element.span().col,
+                            severity: Severity::Error,
+                            message: format!(
+                                "PushingInvalidType: expected {}, found {}",
+                                want.name(),
+                                elem_ty.name()
+                            ),
+                        });
+                    }
+                    Some(_) => {}
+                }
+            }
+            ExprType::List { elem: expected.unwrap_or(ValueType::Unknown), size: elements.len() }
+        }
+        Expr::Index { list, index, span } => {
+            let list_ty = check_expr(list, env, diagnostics);
+            check_expr(index, env, diagnostics);
+
+            if let (ExprType::List { elem, size }, Expr::Number(n, _)) = (list_ty, index.as_ref()) {
+                let idx = *n as i64;
+                if idx < 0 || idx as usize >= size {
+                    diagnostics.push(Diagnostic {
+                        flow_line: span.line,
+                        flow_col: span.col,
+                        severity: Severity::Error,
+                        message: format!("IndexOutOfRange: index {idx} out of range for list of size {size}"),
+                    });
+                }
+                return ExprType::Scalar(elem);
+            }
+
+            ExprType::Scalar(ValueType::Unknown)
+        }
+    }
+}